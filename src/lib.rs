@@ -1,5 +1,9 @@
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::default::Default;
+use std::fs;
+use swc_core::common::comments::Comments;
+use swc_core::common::{errors::HANDLER, BytePos, Span, DUMMY_SP};
 use swc_core::ecma::{
     ast::Program,
     ast::{Str},
@@ -8,87 +12,488 @@ use swc_core::ecma::{
 };
 use swc_core::plugin::{plugin_transform, proxies::TransformPluginProgramMetadata};
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::from_str;
 use swc_core::atoms::Atom;
-use swc_core::ecma::ast::{CallExpr, ExportAll, ExportDefaultDecl, Import, ImportDecl, ImportSpecifier, JSXText, NamedExport, TplElement};
+use swc_core::ecma::ast::{CallExpr, ExportAll, ExportDefaultDecl, Ident, Import, ImportDecl, ImportSpecifier, JSXAttr, JSXText, NamedExport, TplElement};
 use swc_core::ecma::visit::visit_mut_pass;
 use swc_ecma_parser::{EsSyntax, Syntax};
 
 #[derive(Deserialize, Default)]
+struct Rule {
+    pattern: String,
+    replacement: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum MaskMode {
+    Remove,
+    CharCount,
+    Single,
+    Fixed(usize),
+}
+
+#[derive(Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Mode {
+    #[default]
+    Transform,
+    Enforce,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+enum Context {
+    ImportSpecifiers,
+    ExportSources,
+    JsxAttributes,
+    TemplateLiterals,
+    CallArguments,
+    DynamicImportArguments,
+    PlainText,
+    JsxText,
+}
+
+fn default_skip_contexts() -> Vec<Context> {
+    vec![Context::ImportSpecifiers, Context::ExportSources, Context::DynamicImportArguments]
+}
+
+#[derive(Deserialize)]
 struct Config {
     replace_with: Option<String>,
     matches: Vec<String>,
+    #[serde(default)]
+    scripts: Vec<String>,
+    #[serde(default)]
+    blocks: Vec<String>,
+    #[serde(default)]
+    rules: Vec<Rule>,
+    mask_mode: Option<MaskMode>,
+    #[serde(default)]
+    mode: Mode,
+    report_path: Option<String>,
+    #[serde(default = "default_skip_contexts")]
+    skip_contexts: Vec<Context>,
+    #[serde(default)]
+    only_contexts: Vec<Context>,
+    #[serde(default)]
+    scan_comments: bool,
+    #[serde(default)]
+    scan_identifiers: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            replace_with: None,
+            matches: Vec::new(),
+            scripts: Vec::new(),
+            blocks: Vec::new(),
+            rules: Vec::new(),
+            mask_mode: None,
+            mode: Mode::default(),
+            report_path: None,
+            skip_contexts: default_skip_contexts(),
+            only_contexts: Vec::new(),
+            scan_comments: false,
+            scan_identifiers: false,
+        }
+    }
+}
+
+struct CompiledRule {
+    matcher: Regex,
+    replacement: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ReportEntry {
+    pattern: String,
+    matched: String,
+    replacement: String,
+    node_kind: &'static str,
 }
 
 struct RemoveInvalidContent {
-    matchers: Vec<Regex>,
+    matchers: Vec<CompiledRule>,
     replace_with: String,
+    mask_mode: MaskMode,
+    enforce: bool,
+    violations: Vec<(Span, String)>,
+    report_path: Option<String>,
+    report: Vec<ReportEntry>,
+    skip_contexts: HashSet<Context>,
+    only_contexts: HashSet<Context>,
+    scan_comments: bool,
+    scan_identifiers: bool,
+    // Boxed as a trait object so tests can exercise `scan_comments_at`
+    // against an in-process `SingleThreadedComments`, since the concrete
+    // `PluginCommentsProxy` the real plugin host provides only works when
+    // actually hosted by the runtime.
+    comments: Option<Box<dyn Comments>>,
+    current_context: Option<Context>,
+    // `visit_mut_span` fires on every node's span, not just comment
+    // anchors, so many nodes share the same `lo`/`hi` position. Tracks
+    // which positions have already been scanned this run to avoid
+    // taking/re-adding (or double-reporting) the same comment repeatedly.
+    comment_positions_seen: HashSet<BytePos>,
 }
 
 impl RemoveInvalidContent {
-    fn new(config: Config) -> RemoveInvalidContent {
-        Self {
-            matchers: config.matches.iter().map(|x| Regex::new(x.as_str()).unwrap()).collect(),
-            replace_with: config.replace_with.unwrap_or("".to_string()),
+    /// Builds the matcher list and visitor state from `config`, or a
+    /// human-readable message describing which pattern/script/block/rule
+    /// failed to compile, so a bad user config surfaces as a plugin
+    /// diagnostic instead of a raw panic.
+    fn new(config: Config) -> Result<RemoveInvalidContent, String> {
+        let mut matchers: Vec<CompiledRule> = Vec::new();
+
+        for pattern in config.matches.iter() {
+            let matcher = Regex::new(pattern.as_str()).map_err(|e| format!("invalid `matches` pattern {:?}: {}", pattern, e))?;
+            matchers.push(CompiledRule { matcher, replacement: None });
+        }
+
+        for name in config.scripts.iter() {
+            let pattern = format!(r"\p{{Script={}}}", name);
+            let matcher = Regex::new(&pattern).map_err(|e| format!("invalid unicode script {:?}: {}", name, e))?;
+            matchers.push(CompiledRule { matcher, replacement: None });
+        }
+
+        for name in config.blocks.iter() {
+            let pattern = format!(r"\p{{Block={}}}", name);
+            let matcher = Regex::new(&pattern).map_err(|e| format!("invalid unicode block {:?}: {}", name, e))?;
+            matchers.push(CompiledRule { matcher, replacement: None });
         }
+
+        for rule in config.rules.iter() {
+            let matcher = Regex::new(rule.pattern.as_str()).map_err(|e| format!("invalid rule pattern {:?}: {}", rule.pattern, e))?;
+            matchers.push(CompiledRule { matcher, replacement: rule.replacement.clone() });
+        }
+
+        let replace_with = config.replace_with.unwrap_or("".to_string());
+        let mask_mode = config.mask_mode.unwrap_or(if replace_with.is_empty() {
+            MaskMode::Remove
+        } else {
+            MaskMode::CharCount
+        });
+
+        Ok(Self {
+            matchers,
+            replace_with,
+            mask_mode,
+            enforce: config.mode == Mode::Enforce,
+            violations: Vec::new(),
+            report_path: config.report_path,
+            report: Vec::new(),
+            skip_contexts: config.skip_contexts.into_iter().collect(),
+            only_contexts: config.only_contexts.into_iter().collect(),
+            scan_comments: config.scan_comments,
+            scan_identifiers: config.scan_identifiers,
+            comments: None,
+            current_context: None,
+            comment_positions_seen: HashSet::new(),
+        })
     }
 
-    fn replace_with<'h>(&self, matcher: &Regex, str: &'h str) -> Result<Cow<'h, str>, bool> {
-        if !matcher.is_match(str) {
-            return Err(false);
+    /// Whether nodes under `context` should be scanned: an `only_contexts`
+    /// allowlist wins if set, otherwise everything not in `skip_contexts` runs.
+    fn should_process(&self, context: Context) -> bool {
+        if !self.only_contexts.is_empty() {
+            return self.only_contexts.contains(&context);
+        }
+        !self.skip_contexts.contains(&context)
+    }
+
+    /// Descends into `node` under `context` if it passes `should_process`,
+    /// tracking `context` as the nearest enclosing container so nested
+    /// string/JSX-text nodes can tell which allowlist/skip rule put them
+    /// there instead of always falling back to `PlainText`/`JsxText`.
+    fn visit_with_context<N: VisitMutWith<Self>>(&mut self, context: Context, node: &mut N) {
+        if !self.should_process(context) {
+            return;
+        }
+        let prev = self.current_context.replace(context);
+        node.visit_mut_children_with(self);
+        self.current_context = prev;
+    }
+
+    fn redact_text(&self, text: &str) -> String {
+        let mut current = text.to_string();
+        for rule in self.matchers.iter() {
+            if let Ok(new_value) = self.replace_with(rule, &current) {
+                current = new_value.into_owned();
+            }
+        }
+        current
+    }
+
+    /// Scans the comment(s) attached at `pos`, if any, through the same
+    /// matchers used for string/JSX/template content. `PluginCommentsProxy`
+    /// only supports lookup by position, so this is called from
+    /// `visit_mut_span` for every span the visitor passes through — which
+    /// means the same `pos` shows up repeatedly for nested nodes that share
+    /// a start/end offset. `comment_positions_seen` makes each position a
+    /// one-time scan so a comment is never redacted (or double-reported)
+    /// more than once.
+    fn scan_comments_at(&mut self, pos: BytePos) {
+        if !self.comment_positions_seen.insert(pos) {
+            return;
+        }
+
+        let Some(mut comments) = self.comments.take() else {
+            return;
+        };
+
+        if let Some(mut leading) = comments.take_leading(pos) {
+            if self.enforce {
+                for comment in leading.iter() {
+                    self.record_violations(comment.span, comment.text.as_str());
+                }
+            } else {
+                for comment in leading.iter_mut() {
+                    comment.text = Atom::from(self.redact_text(comment.text.as_str()));
+                }
+            }
+            comments.add_leading_comments(pos, leading);
         }
 
-        Ok(matcher.replace_all(str, |caps: &regex::Captures| {
-            if self.replace_with.is_empty() {
-                return "".to_string();
+        if let Some(mut trailing) = comments.take_trailing(pos) {
+            if self.enforce {
+                for comment in trailing.iter() {
+                    self.record_violations(comment.span, comment.text.as_str());
+                }
+            } else {
+                for comment in trailing.iter_mut() {
+                    comment.text = Atom::from(self.redact_text(comment.text.as_str()));
+                }
+            }
+            comments.add_trailing_comments(pos, trailing);
+        }
+
+        self.comments = Some(comments);
+    }
+
+    /// Records every matched substring of `str` against `span` without
+    /// mutating the AST. Used when `mode: enforce` turns the plugin into a
+    /// build-time guard instead of a silent rewriter.
+    fn record_violations(&mut self, span: Span, str: &str) {
+        for rule in self.matchers.iter() {
+            for matched in rule.matcher.find_iter(str) {
+                self.violations.push((span, matched.as_str().to_string()));
             }
+        }
+    }
+
+    fn render_replacement(&self, rule: &CompiledRule, caps: &regex::Captures) -> String {
+        if let Some(template) = &rule.replacement {
+            let mut dst = String::new();
+            caps.expand(template, &mut dst);
+            return dst;
+        }
 
-            let matched_str = &caps[0];
-            self.replace_with.repeat(matched_str.len())
-        }))
+        if self.replace_with.is_empty() {
+            return "".to_string();
+        }
+
+        let matched_str = &caps[0];
+        match self.mask_mode {
+            MaskMode::Remove => "".to_string(),
+            MaskMode::CharCount => self.replace_with.repeat(matched_str.chars().count()),
+            MaskMode::Single => self.replace_with.clone(),
+            MaskMode::Fixed(n) => self.replace_with.repeat(n),
+        }
+    }
+
+    fn replace_with<'h>(&self, rule: &CompiledRule, str: &'h str) -> Result<Cow<'h, str>, bool> {
+        if !rule.matcher.is_match(str) {
+            return Err(false);
+        }
+
+        Ok(rule.matcher.replace_all(str, |caps: &regex::Captures| self.render_replacement(rule, caps)))
+    }
+
+    /// Builds one `ReportEntry` per match of `rule` in `str`, for `report_path` auditing.
+    fn collect_report_entries(&self, rule: &CompiledRule, node_kind: &'static str, str: &str) -> Vec<ReportEntry> {
+        rule.matcher
+            .captures_iter(str)
+            .map(|caps| ReportEntry {
+                pattern: rule.matcher.as_str().to_string(),
+                matched: caps[0].to_string(),
+                replacement: self.render_replacement(rule, &caps),
+                node_kind,
+            })
+            .collect()
     }
 }
 
 
+/// Conservative check for whether `name` could still be a valid JS
+/// identifier after redaction (used to avoid writing back text that would
+/// make the surrounding declaration/reference unparsable).
+fn is_ident_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    (first.is_alphabetic() || first == '_' || first == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
 impl VisitMut for RemoveInvalidContent {
-    fn visit_mut_export_all(&mut self, _: &mut ExportAll) {}
-    fn visit_mut_named_export(&mut self, _: &mut NamedExport) {}
-    fn visit_mut_export_default_decl(&mut self, _: &mut ExportDefaultDecl) {}
-    fn visit_mut_import(&mut self, _: &mut Import) {}
-    fn visit_mut_import_decl(&mut self, _: &mut ImportDecl) {}
-    fn visit_mut_import_specifier(&mut self, _: &mut ImportSpecifier) {}
+    fn visit_mut_export_all(&mut self, node: &mut ExportAll) {
+        self.visit_with_context(Context::ExportSources, node);
+    }
+    fn visit_mut_named_export(&mut self, node: &mut NamedExport) {
+        self.visit_with_context(Context::ExportSources, node);
+    }
+    fn visit_mut_export_default_decl(&mut self, node: &mut ExportDefaultDecl) {
+        self.visit_with_context(Context::ExportSources, node);
+    }
+    fn visit_mut_import(&mut self, node: &mut Import) {
+        self.visit_with_context(Context::ImportSpecifiers, node);
+    }
+    fn visit_mut_import_decl(&mut self, node: &mut ImportDecl) {
+        self.visit_with_context(Context::ImportSpecifiers, node);
+    }
+    fn visit_mut_import_specifier(&mut self, node: &mut ImportSpecifier) {
+        self.visit_with_context(Context::ImportSpecifiers, node);
+    }
+
+    fn visit_mut_jsx_attr(&mut self, node: &mut JSXAttr) {
+        self.visit_with_context(Context::JsxAttributes, node);
+    }
 
     fn visit_mut_call_expr(&mut self, node: &mut CallExpr) {
-        if !node.callee.is_import() {
-            node.visit_mut_children_with(self);
+        // Dynamic `import(...)` gets its own context so it keeps its old
+        // default-skipped behavior while still being overridable through
+        // `skip_contexts`/`only_contexts` like every other context.
+        let context = if node.callee.is_import() {
+            Context::DynamicImportArguments
+        } else {
+            Context::CallArguments
+        };
+        self.visit_with_context(context, node);
+    }
+
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        if self.scan_comments {
+            self.scan_comments_at(span.lo);
+            self.scan_comments_at(span.hi);
+        }
+    }
+
+    fn visit_mut_ident(&mut self, node: &mut Ident) {
+        if self.scan_identifiers {
+            if self.enforce {
+                self.record_violations(node.span, node.sym.as_str());
+            } else {
+                let redacted = self.redact_text(node.sym.as_str());
+                // An identifier that is redacted down to nothing (or to text
+                // that can no longer parse as one) would produce invalid JS
+                // like `const  = 1;`, so leave the original name in place.
+                if redacted != node.sym.as_str() && !redacted.is_empty() && is_ident_name(&redacted) {
+                    node.sym = Atom::from(redacted);
+                }
+            }
         }
+        node.visit_mut_children_with(self);
     }
 
     fn visit_mut_jsx_text(&mut self, node: &mut JSXText) {
-        for matcher in self.matchers.iter() {
-            if let Ok(new_value) = self.replace_with(matcher, node.raw.as_str()) {
+        let context = self.current_context.unwrap_or(Context::JsxText);
+        if !self.should_process(context) {
+            node.visit_mut_children_with(self);
+            return;
+        }
+
+        if self.enforce {
+            self.record_violations(node.span, node.raw.as_str());
+            node.visit_mut_children_with(self);
+            return;
+        }
+
+        for i in 0..self.matchers.len() {
+            let (new_value, entries) = {
+                let rule = &self.matchers[i];
+                let new_value = self.replace_with(rule, node.raw.as_str());
+                let entries = if self.report_path.is_some() {
+                    self.collect_report_entries(rule, "jsx_text", node.raw.as_str())
+                } else {
+                    Vec::new()
+                };
+                (new_value, entries)
+            };
+
+            if let Ok(new_value) = new_value {
                 let new_atom = Atom::from(new_value);
                 node.raw.clone_from(&new_atom);
                 node.value.clone_from(&new_atom);
             }
+            self.report.extend(entries);
         }
 
         node.visit_mut_children_with(self);
     }
 
     fn visit_mut_str(&mut self, node: &mut Str) {
-        for matcher in self.matchers.iter() {
-            if let Ok(new_value) = self.replace_with(matcher, node.value.as_str()) {
+        let context = self.current_context.unwrap_or(Context::PlainText);
+        if !self.should_process(context) {
+            node.visit_mut_children_with(self);
+            return;
+        }
+
+        if self.enforce {
+            self.record_violations(node.span, node.value.as_str());
+            node.visit_mut_children_with(self);
+            return;
+        }
+
+        for i in 0..self.matchers.len() {
+            let (new_value, entries) = {
+                let rule = &self.matchers[i];
+                let new_value = self.replace_with(rule, node.value.as_str());
+                let entries = if self.report_path.is_some() {
+                    self.collect_report_entries(rule, "string", node.value.as_str())
+                } else {
+                    Vec::new()
+                };
+                (new_value, entries)
+            };
+
+            if let Ok(new_value) = new_value {
                 node.clone_from(&Str::from(new_value.to_string()))
             }
+            self.report.extend(entries);
         }
         node.visit_mut_children_with(self);
     }
 
     fn visit_mut_tpl_element(&mut self, node: &mut TplElement) {
-        for matcher in self.matchers.iter() {
-            if let Ok(raw_value) = self.replace_with(matcher, node.raw.as_str()) {
+        let context = self.current_context.unwrap_or(Context::TemplateLiterals);
+        if !self.should_process(context) {
+            node.visit_mut_children_with(self);
+            return;
+        }
+
+        if self.enforce {
+            self.record_violations(node.span, node.raw.as_str());
+            node.visit_mut_children_with(self);
+            return;
+        }
+
+        for i in 0..self.matchers.len() {
+            let (raw_value, entries) = {
+                let rule = &self.matchers[i];
+                let raw_value = self.replace_with(rule, node.raw.as_str());
+                let entries = if self.report_path.is_some() {
+                    self.collect_report_entries(rule, "template_element", node.raw.as_str())
+                } else {
+                    Vec::new()
+                };
+                (raw_value, entries)
+            };
+
+            if let Ok(raw_value) = raw_value {
                 let cooked_value = Str::from_tpl_raw(&raw_value);
                 let new_atom = Atom::from(raw_value);
 
@@ -101,6 +506,7 @@ impl VisitMut for RemoveInvalidContent {
 
                 node.clone_from(&tpl_element)
             }
+            self.report.extend(entries);
         }
         node.visit_mut_children_with(self);
     }
@@ -117,7 +523,34 @@ pub fn process_transform(mut program: Program, data: TransformPluginProgramMetad
         .expect("invalid packages")
         .unwrap_or(Config::default());
 
-    program.visit_mut_with(&mut RemoveInvalidContent::new(config));
+    let mut visitor = match RemoveInvalidContent::new(config) {
+        Ok(visitor) => visitor,
+        Err(message) => {
+            HANDLER.with(|handler| {
+                handler
+                    .struct_span_err(DUMMY_SP, &format!("swc-remove-matched-charset-plugin: {}", message))
+                    .emit();
+            });
+            return program;
+        }
+    };
+    visitor.comments = data.comments.map(|comments| Box::new(comments) as Box<dyn Comments>);
+    program.visit_mut_with(&mut visitor);
+
+    if !visitor.violations.is_empty() {
+        HANDLER.with(|handler| {
+            for (span, matched) in visitor.violations.iter() {
+                handler
+                    .struct_span_err(*span, &format!("matched disallowed content: {:?}", matched))
+                    .emit();
+            }
+        });
+    }
+
+    if let Some(report_path) = &visitor.report_path {
+        let report_json = serde_json::to_string_pretty(&visitor.report).expect("failed to serialize transform report");
+        fs::write(report_path, report_json).expect("failed to write transform report");
+    }
 
     program
 }
@@ -128,7 +561,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"[\u4E00-\u9FFF]".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_not_change,
     r#"console.log("transform");"#,
     r#"console.log("transform");"#
@@ -139,7 +572,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"[\u4E00-\u9FFF]".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_remove_in_method_calls,
     r#"console.log("transform中文");"#,
     r#"console.log("transform");"#
@@ -152,7 +585,7 @@ test_inline!(
         matches: vec![r"[\u4E00-\u9FFF]".to_string()],
         ..Default::default()
     }
-    )),
+    ).unwrap()),
     should_remove_in_object_property,
     r#"const a = {
       cde: {
@@ -178,7 +611,7 @@ test_inline!(
         matches: vec![r"[\u4E00-\u9FFF]".to_string()],
         ..Default::default()
     }
-    )),
+    ).unwrap()),
     should_left_english_and_special_characters,
     r#"const a = {
       abc: {
@@ -202,7 +635,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_remove_url,
     r#"console.log("https://abc.com/faker-url");"#,
     r#"console.log("https:///faker-url");"#
@@ -215,7 +648,7 @@ test_inline!(
         matches: vec![r"[\u4E00-\u9FFF]".to_string()],
         ..Default::default()
     }
-    )),
+    ).unwrap()),
     should_not_remove_slack,
     r#"const a = {
       cde: {
@@ -239,7 +672,7 @@ test_inline!(
         matches: vec![r"[\u4E00-\u9FFF]".to_string()],
         ..Default::default()
     }
-    )),
+    ).unwrap()),
     should_not_remove_slack_from_tpl,
     r#"const a = `\\中文${b}`"#,
     r#"const a = `\\${b}`"#
@@ -255,7 +688,7 @@ test_inline!(
         matches: vec![r"[\u4E00-\u9FFF]".to_string()],
         ..Default::default()
     }
-    )),
+    ).unwrap()),
     should_remove_chinese_on_jsx,
     r#"const a = () => {
         return <div>关闭
@@ -281,7 +714,7 @@ test_inline!(
         matches: vec![r"[\u4E00-\u9FFF]".to_string()],
         ..Default::default()
     }
-    )),
+    ).unwrap()),
     should_remove_chinese_on_jsx_attr,
     r#"const a = () => {
         return <div data-info="中文">
@@ -302,7 +735,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_remove_from_tpl,
     r#"console.log(`https://abc.com/faker-url/${window.location.href}`);"#,
     r#"console.log(`https:///faker-url/${window.location.href}`);"#
@@ -313,7 +746,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_not_remove_from_import_all,
     r#"import * as A from "/abc.com/faker-url";"#,
     r#"import * as A from "/abc.com/faker-url";"#
@@ -324,7 +757,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_not_remove_from_import_only,
     r#"import "/abc.com/faker-url";"#,
     r#"import "/abc.com/faker-url";"#
@@ -335,7 +768,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_not_remove_from_default_import,
     r#"import abc from "/abc.com/faker-url";"#,
     r#"import abc from "/abc.com/faker-url";"#
@@ -347,7 +780,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_not_remove_from_named_import,
     r#"import { efg } from "/abc.com/faker-url";"#,
     r#"import { efg } from "/abc.com/faker-url";"#
@@ -358,7 +791,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_not_remove_from_dynamic_import,
     r#"import("/abc.com/faker-url");"#,
     r#"import("/abc.com/faker-url");"#
@@ -369,7 +802,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_not_remove_from_all_export,
     r#"export * from "/abc.com/faker-url";"#,
     r#"export * from "/abc.com/faker-url";"#
@@ -380,7 +813,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_not_remove_from_all_export_with_rename,
     r#"export * as a from "/abc.com/faker-url";"#,
     r#"export * as a from "/abc.com/faker-url";"#
@@ -391,7 +824,7 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         ..Default::default()
-    })),
+    }).unwrap()),
     should_not_remove_from_named_export,
     r#"export { cde } from "/abc.com/faker-url";"#,
     r#"export { cde } from "/abc.com/faker-url";"#
@@ -402,10 +835,261 @@ test_inline!(
     |_| visit_mut_pass(RemoveInvalidContent::new(Config{
         matches: vec![r"abc.com|cde.org".to_string()],
         replace_with: Some(String::from("*"))
-    })),
+        , ..Default::default()
+    }).unwrap()),
     should_replace_as_passed_char,
     r#"console.log("https://abc.com/faker-url");"#,
     r#"console.log("https://*******/faker-url");"#
 );
 
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        scripts: vec!["Han".to_string()],
+        ..Default::default()
+    }).unwrap()),
+    should_remove_by_script_name,
+    r#"console.log("transform中文");"#,
+    r#"console.log("transform");"#
+);
+
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        rules: vec![Rule{
+            pattern: r"(https?://)abc\.com".to_string(),
+            replacement: Some("${1}example.com".to_string()),
+        }],
+        ..Default::default()
+    }).unwrap()),
+    should_rewrite_with_capture_group_template,
+    r#"console.log("https://abc.com/faker-url");"#,
+    r#"console.log("https://example.com/faker-url");"#
+);
+
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"[一-鿿]+".to_string()],
+        replace_with: Some(String::from("*")),
+        mask_mode: Some(MaskMode::CharCount),
+        ..Default::default()
+    }).unwrap()),
+    should_mask_by_char_count_not_byte_len,
+    r#"console.log("视频下载错误");"#,
+    r#"console.log("******");"#
+);
+
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"[一-鿿]+".to_string()],
+        replace_with: Some(String::from("*")),
+        mask_mode: Some(MaskMode::Single),
+        ..Default::default()
+    }).unwrap()),
+    should_mask_with_single_fill_char,
+    r#"console.log("视频下载错误");"#,
+    r#"console.log("*");"#
+);
+
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"[一-鿿]".to_string()],
+        mode: Mode::Enforce,
+        ..Default::default()
+    }).unwrap()),
+    should_leave_ast_unchanged_in_enforce_mode,
+    r#"console.log("transform中文");"#,
+    r#"console.log("transform中文");"#
+);
+
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"[一-鿿]".to_string()],
+        report_path: Some("/tmp/swc-remove-matched-charset-plugin-test-report.json".to_string()),
+        ..Default::default()
+    }).unwrap()),
+    should_still_transform_when_report_path_is_set,
+    r#"console.log("transform中文");"#,
+    r#"console.log("transform");"#
+);
+
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"abc.com|cde.org".to_string()],
+        skip_contexts: vec![],
+        ..Default::default()
+    }).unwrap()),
+    should_scrub_import_specifiers_when_not_skipped,
+    r#"import "/abc.com/faker-url";"#,
+    r#"import "//faker-url";"#
+);
+
+test_inline!(
+    Syntax::Es(EsSyntax {
+        jsx: true,
+        ..Default::default()
+    }),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"[一-鿿]".to_string()],
+        only_contexts: vec![Context::JsxAttributes],
+        ..Default::default()
+    }).unwrap()),
+    should_restrict_to_only_contexts,
+    r#"const a = () => {
+        console.log("中文");
+        return <div data-info="中文">
+            <p>node</p>
+        </div>
+    }
+    "#,
+    r#"const a = () => {
+        console.log("中文");
+        return <div data-info="">
+            <p>node</p>
+        </div>
+    }"#
+);
+
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"[一-鿿]".to_string()],
+        scan_identifiers: true,
+        ..Default::default()
+    }).unwrap()),
+    should_redact_identifiers_when_enabled,
+    r#"const a中文 = 1;"#,
+    r#"const a = 1;"#
+);
+
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"[一-鿿]".to_string()],
+        scan_identifiers: true,
+        ..Default::default()
+    }).unwrap()),
+    should_leave_identifier_unchanged_when_fully_redacted,
+    r#"const 中文 = 1;"#,
+    r#"const 中文 = 1;"#
+);
+
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"[一-鿿]".to_string()],
+        only_contexts: vec![Context::PlainText],
+        ..Default::default()
+    }).unwrap()),
+    should_restrict_plain_strings_to_only_contexts,
+    r#"console.log("中文");
+    const a = "中文";"#,
+    r#"console.log("中文");
+    const a = "";"#
+);
+
+// A template literal nested inside an allow-listed container should inherit
+// that ancestor context instead of being gated solely by whether
+// `TemplateLiterals` itself is in `only_contexts`.
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"[一-鿿]".to_string()],
+        only_contexts: vec![Context::CallArguments],
+        ..Default::default()
+    }).unwrap()),
+    should_restrict_tpl_in_call_args_to_only_contexts,
+    r#"console.log(`中文`);
+    const a = `中文`;"#,
+    r#"console.log(``);
+    const a = `中文`;"#
+);
+
+// `test_inline!` only diffs the transformed AST, so it can't exercise
+// `scan_comments_at`'s side-channel writes through the `Comments` trait.
+// `SingleThreadedComments` is an in-process `Comments` impl we can seed and
+// inspect directly, standing in for the host-bound `PluginCommentsProxy`.
+#[cfg(test)]
+#[test]
+fn should_redact_matched_content_in_comments() {
+    use swc_core::common::comments::{Comment, CommentKind, SingleThreadedComments};
+
+    let mut visitor = RemoveInvalidContent::new(Config {
+        matches: vec![r"[一-鿿]".to_string()],
+        scan_comments: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let comments = SingleThreadedComments::default();
+    let pos = BytePos(1);
+    comments.add_leading(
+        pos,
+        Comment {
+            kind: CommentKind::Line,
+            span: DUMMY_SP,
+            text: Atom::from(" transform中文"),
+        },
+    );
+
+    visitor.comments = Some(Box::new(comments.clone()));
+    visitor.scan_comments_at(pos);
+
+    let leading = comments.take_leading(pos).expect("leading comment should still be attached");
+    assert_eq!(leading[0].text.as_str(), " transform");
+}
+
+// `visit_mut_span` used to skip the `self.enforce` check that
+// `visit_mut_str`/`visit_mut_jsx_text`/`visit_mut_tpl_element` all honor, so
+// `mode: enforce` combined with `scan_comments` would silently rewrite
+// comment text instead of leaving it untouched and reporting a violation.
+#[cfg(test)]
+#[test]
+fn should_report_instead_of_rewrite_comments_in_enforce_mode() {
+    use swc_core::common::comments::{Comment, CommentKind, SingleThreadedComments};
+
+    let mut visitor = RemoveInvalidContent::new(Config {
+        matches: vec![r"[一-鿿]".to_string()],
+        mode: Mode::Enforce,
+        scan_comments: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let comments = SingleThreadedComments::default();
+    let pos = BytePos(1);
+    comments.add_leading(
+        pos,
+        Comment {
+            kind: CommentKind::Line,
+            span: DUMMY_SP,
+            text: Atom::from(" transform中文"),
+        },
+    );
+    visitor.comments = Some(Box::new(comments.clone()));
+    visitor.scan_comments_at(pos);
+
+    let leading = comments.take_leading(pos).expect("leading comment should still be attached");
+    assert_eq!(leading[0].text.as_str(), " transform中文");
+    assert_eq!(visitor.violations.len(), 2);
+}
+
+test_inline!(
+    Default::default(),
+    |_| visit_mut_pass(RemoveInvalidContent::new(Config{
+        matches: vec![r"[一-鿿]".to_string()],
+        mode: Mode::Enforce,
+        scan_identifiers: true,
+        ..Default::default()
+    }).unwrap()),
+    should_leave_identifiers_unchanged_in_enforce_mode,
+    r#"const a中文 = 1;"#,
+    r#"const a中文 = 1;"#
+);
+
 